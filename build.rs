@@ -0,0 +1,116 @@
+use std::{env, fs, path::Path};
+
+// (dispatch slot, mnemonic, handler function name in `cpu::execute`)
+// anything not listed here falls back to `op_illegal` / "ILL"
+const PRIMARY_OPCODES: &[(usize, &str, &str)] = &[
+  (0x00, "SPECIAL", "op_special"),
+  (0x01, "BCOND",   "op_bcond"),
+  (0x02, "J",       "op_j"),
+  (0x03, "JAL",     "op_jal"),
+  (0x04, "BEQ",     "op_beq"),
+  (0x05, "BNE",     "op_bne"),
+  (0x06, "BLEZ",    "op_blez"),
+  (0x07, "BGTZ",    "op_bgtz"),
+  (0x08, "ADDI",    "op_addi"),
+  (0x09, "ADDIU",   "op_addiu"),
+  (0x0a, "SLTI",    "op_slti"),
+  (0x0b, "SLTIU",   "op_sltiu"),
+  (0x0c, "ANDI",    "op_andi"),
+  (0x0d, "ORI",     "op_ori"),
+  (0x0e, "XORI",    "op_xori"),
+  (0x0f, "LUI",     "op_lui"),
+  (0x10, "COP0",    "op_cop0"),
+  (0x11, "COP1",    "op_cop_illegal"),
+  (0x12, "COP2",    "op_cop2"),
+  (0x13, "COP3",    "op_cop_illegal"),
+  (0x20, "LB",      "op_lb"),
+  (0x21, "LH",      "op_lh"),
+  (0x22, "LWL",     "op_lwl"),
+  (0x23, "LW",      "op_lw"),
+  (0x24, "LBU",     "op_lbu"),
+  (0x25, "LHU",     "op_lhu"),
+  (0x26, "LWR",     "op_lwr"),
+  (0x28, "SB",      "op_sb"),
+  (0x29, "SH",      "op_sh"),
+  (0x2a, "SWL",     "op_swl"),
+  (0x2b, "SW",      "op_sw"),
+  (0x2e, "SWR",     "op_swr"),
+  (0x30, "LWC0",    "op_cop_illegal"),
+  (0x31, "LWC1",    "op_cop_illegal"),
+  (0x32, "LWC2",    "op_lwc2"),
+  (0x33, "LWC3",    "op_cop_illegal"),
+  (0x38, "SWC0",    "op_cop_illegal"),
+  (0x39, "SWC1",    "op_cop_illegal"),
+  (0x3a, "SWC2",    "op_swc2"),
+  (0x3b, "SWC3",    "op_cop_illegal"),
+];
+
+const SPECIAL_FUNCTS: &[(usize, &str, &str)] = &[
+  (0x00, "SLL",     "op_sll"),
+  (0x02, "SRL",     "op_srl"),
+  (0x03, "SRA",     "op_sra"),
+  (0x04, "SLLV",    "op_sllv"),
+  (0x06, "SRLV",    "op_srlv"),
+  (0x07, "SRAV",    "op_srav"),
+  (0x08, "JR",      "op_jr"),
+  (0x09, "JALR",    "op_jalr"),
+  (0x0c, "SYSCALL", "op_syscall"),
+  (0x0d, "BREAK",   "op_break"),
+  (0x10, "MFHI",    "op_mfhi"),
+  (0x11, "MTHI",    "op_mthi"),
+  (0x12, "MFLO",    "op_mflo"),
+  (0x13, "MTLO",    "op_mtlo"),
+  (0x18, "MULT",    "op_mult"),
+  (0x19, "MULTU",   "op_multu"),
+  (0x1a, "DIV",     "op_div"),
+  (0x1b, "DIVU",    "op_divu"),
+  (0x20, "ADD",     "op_add"),
+  (0x21, "ADDU",    "op_addu"),
+  (0x22, "SUB",     "op_sub"),
+  (0x23, "SUBU",    "op_subu"),
+  (0x24, "AND",     "op_and"),
+  (0x25, "OR",      "op_or"),
+  (0x26, "XOR",     "op_xor"),
+  (0x27, "NOR",     "op_nor"),
+  (0x2a, "SLT",     "op_slt"),
+  (0x2b, "SLTU",    "op_sltu"),
+];
+
+fn generate_table(out_dir: &Path, file_name: &str, table_name: &str, entries: &[(usize, &str, &str)]) {
+  let mut rust = format!("pub static {table_name}: [fn(&mut CPU, Instruction); 64] = [\n");
+
+  for slot in 0..64 {
+    let handler = entries.iter().find(|(index, _, _)| *index == slot).map_or("op_illegal", |(_, _, handler)| handler);
+    rust.push_str(&format!("  CPU::{handler},\n"));
+  }
+
+  rust.push_str("];\n");
+
+  fs::write(out_dir.join(file_name), rust).expect("failed to write generated opcode table");
+}
+
+fn generate_mnemonics(out_dir: &Path, file_name: &str, entries: &[(usize, &str, &str)]) {
+  let mut rust = String::from("[\n");
+
+  for slot in 0..64 {
+    let mnemonic = entries.iter().find(|(index, _, _)| *index == slot).map_or("ILL", |(_, mnemonic, _)| *mnemonic);
+    rust.push_str(&format!("  \"{mnemonic}\",\n"));
+  }
+
+  rust.push_str("]\n");
+
+  fs::write(out_dir.join(file_name), rust).expect("failed to write generated mnemonic table");
+}
+
+fn main() {
+  let out_dir = env::var("OUT_DIR").expect("OUT_DIR not set by cargo");
+  let out_dir = Path::new(&out_dir);
+
+  generate_table(out_dir, "primary_table.rs", "PRIMARY_TABLE", PRIMARY_OPCODES);
+  generate_table(out_dir, "special_table.rs", "SPECIAL_TABLE", SPECIAL_FUNCTS);
+
+  generate_mnemonics(out_dir, "primary_mnemonics.rs", PRIMARY_OPCODES);
+  generate_mnemonics(out_dir, "special_mnemonics.rs", SPECIAL_FUNCTS);
+
+  println!("cargo:rerun-if-changed=build.rs");
+}
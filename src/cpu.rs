@@ -1,8 +1,8 @@
-use std::{rc::Rc, cell::Cell};
+use std::{rc::Rc, cell::Cell, collections::HashSet};
 
 use crate::{cpu::instruction::Instruction, gpu::{CYCLES_PER_SCANLINE, NUM_SCANLINES_PER_FRAME, GPU_FREQUENCY}};
 
-use self::{bus::Bus, dma::DMA, interrupt::interrupt_registers::InterruptRegisters};
+use self::{bus::Bus, dma::DMA, icache::ICache, interrupt::interrupt_registers::{InterruptRegisters, Interrupt}, memory_interface::MemoryInterface, scheduler::{EventType, Scheduler}};
 
 pub mod bus;
 pub mod execute;
@@ -10,12 +10,22 @@ pub mod instruction;
 pub mod dma;
 pub mod counter;
 pub mod interrupt;
+pub mod scheduler;
+pub mod savestate;
+pub mod icache;
+pub mod memory_interface;
 
 // 33.868MHZ
 pub const CPU_FREQUENCY: f64 = 33_868_800.0;
 
 pub const CYCLES_PER_FRAME: i64 = ((CYCLES_PER_SCANLINE * NUM_SCANLINES_PER_FRAME) as f64 * (CPU_FREQUENCY / GPU_FREQUENCY)) as i64;
 
+// KSEG1 (0xa000_0000-0xbfff_ffff) is the uncached mirror of KUSEG/KSEG0; the
+// I-cache must never be consulted for addresses in it
+fn is_cacheable(address: u32) -> bool {
+  address & 0xe000_0000 != 0xa000_0000
+}
+
 #[derive(Clone, Copy)]
 pub enum Cause {
   Interrupt = 0x0,
@@ -29,6 +39,20 @@ pub enum Cause {
 
 }
 
+// reported back to whatever drove `CPU::step` (normally the `debugger`
+// module) so it knows why execution paused
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StopReason {
+  Breakpoint(u32),
+  Watchpoint { address: u32, access: WatchKind }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WatchKind {
+  Read,
+  Write
+}
+
 pub struct COP0 {
   pub sr: u32,
   pub cause: u32,
@@ -44,6 +68,13 @@ impl COP0 {
     self.sr & 0x10000 == 0
   }
 
+  // Swc (SR bit 17): while the cache is isolated, this picks whether an
+  // isolated store fills the matching cache line with the written word
+  // (normal) or just invalidates it, used by the BIOS's bulk cache-clear loop
+  pub fn is_cache_invalidate_mode(&self) -> bool {
+    self.sr & 0x20000 != 0
+  }
+
   pub fn interrupts_ready(&self) -> bool {
     self.sr & 0b1 == 1 && self.interrupt_mask() != 0
   }
@@ -99,7 +130,13 @@ pub struct CPU {
   free_cycles: [u16; 32],
   free_cycles_reg: usize,
   dma: DMA,
-  interrupts: Rc<Cell<InterruptRegisters>>
+  interrupts: Rc<Cell<InterruptRegisters>>,
+  scheduler: Scheduler,
+  breakpoints: HashSet<u32>,
+  watchpoints: HashSet<u32>,
+  last_stop: Option<StopReason>,
+  suppress_breakpoint: bool,
+  icache: ICache
 }
 
 impl CPU {
@@ -125,10 +162,87 @@ impl CPU {
       free_cycles: [0; 32],
       free_cycles_reg: 0,
       dma: DMA::new(interrupts.clone()),
-      interrupts
+      interrupts,
+      scheduler: Scheduler::new(),
+      breakpoints: HashSet::new(),
+      watchpoints: HashSet::new(),
+      last_stop: None,
+      suppress_breakpoint: false,
+      icache: ICache::new()
+    }
+  }
+
+  pub fn add_breakpoint(&mut self, address: u32) {
+    self.breakpoints.insert(address);
+  }
+
+  pub fn remove_breakpoint(&mut self, address: u32) {
+    self.breakpoints.remove(&address);
+  }
+
+  pub fn add_watchpoint(&mut self, address: u32) {
+    self.watchpoints.insert(address);
+  }
+
+  pub fn remove_watchpoint(&mut self, address: u32) {
+    self.watchpoints.remove(&address);
+  }
+
+  pub fn current_pc(&self) -> u32 {
+    self.current_pc
+  }
+
+  // consumes the reason `step` paused for, so the same stop isn't reported twice
+  pub fn take_stop_reason(&mut self) -> Option<StopReason> {
+    self.last_stop.take()
+  }
+
+  // lets the debugger re-enter an instruction whose address is still a
+  // breakpoint without immediately re-tripping it
+  pub fn resume_past_breakpoint(&mut self) {
+    self.suppress_breakpoint = true;
+  }
+
+  fn check_watchpoint(&mut self, address: u32, access: WatchKind) {
+    if self.watchpoints.contains(&address) {
+      self.last_stop = Some(StopReason::Watchpoint { address, access });
     }
   }
 
+  pub fn register_dump(&self) -> String {
+    let mut dump = format!("pc: {:08x}  next_pc: {:08x}  hi: {:08x}  low: {:08x}\n", self.pc, self.next_pc, self.hi, self.low);
+
+    for (i, reg) in self.r.iter().enumerate() {
+      dump.push_str(&format!("r{i:<2}: {reg:08x}  "));
+
+      if i % 4 == 3 {
+        dump.push('\n');
+      }
+    }
+
+    dump.push_str(&format!("\nsr: {:08x}  cause: {:08x}  epc: {:08x}\n", self.cop0.sr, self.cop0.cause, self.cop0.epc));
+
+    dump
+  }
+
+  pub fn memory_dump(&mut self, start: u32, len: u32) -> String {
+    let mut dump = String::new();
+
+    for offset in (0..len).step_by(16) {
+      let address = start.wrapping_add(offset);
+
+      dump.push_str(&format!("{address:08x}: "));
+
+      for i in 0..16.min(len - offset) {
+        dump.push_str(&format!("{:02x} ", self.bus.mem_read_8(address.wrapping_add(i))));
+      }
+
+      dump.push('\n');
+    }
+
+    dump
+  }
+
   pub fn exception(&mut self, cause: Cause) {
     let exception_address = self.cop0.enter_exception(cause);
 
@@ -162,13 +276,20 @@ impl CPU {
         }
       } else {
         let count = self.dma.tick(&mut self.bus);
-        self.bus.counter.tick(count);
+        self.tick(count as i64);
         return;
       }
     }
 
     self.current_pc = self.pc;
 
+    if !self.suppress_breakpoint && self.breakpoints.contains(&self.current_pc) {
+      self.last_stop = Some(StopReason::Breakpoint(self.current_pc));
+      return;
+    }
+
+    self.suppress_breakpoint = false;
+
     if self.current_pc & 0b11 != 0 {
       self.exception(Cause::LoadAddressError);
       return;
@@ -178,8 +299,6 @@ impl CPU {
 
     let instr = self.fetch_instruction();
 
-    // println!("executing instruction {:032b} at address {:08x}", instr, self.current_pc);
-
     // check if we need to handle an interrupt by checking cop0 status register and interrupt mask bits in cause and sr
     if self.cop0.interrupts_ready() {
       self.exception(Cause::Interrupt);
@@ -207,73 +326,82 @@ impl CPU {
   }
 
   pub fn fetch_instruction(&mut self) -> u32 {
-    self.bus.counter.tick(4);
+    // every fetch from a cacheable (KUSEG/KSEG0) address goes through the
+    // I-cache: Isc (SR bit 16) only isolates stores from RAM (see
+    // `MemoryInterface::write`), it isn't a cache enable switch, and real
+    // games run with Isc=0 for virtually their entire lifetime. KSEG1
+    // (0xa000_0000-0xbfff_ffff) is never cached on real hardware - the CPU
+    // boots into it (BIOS at 0xbfc0_0000) specifically to bypass the cache
+    if !is_cacheable(self.pc) {
+      self.tick(4);
+      return self.bus.mem_read_32(self.pc, false);
+    }
 
-    // TODO: add caching code later
+    if let Some(word) = self.icache.lookup(self.pc) {
+      self.tick(1);
+      return word;
+    }
 
-    self.bus.mem_read_32(self.pc, false)
-  }
+    let line_base = self.pc & !0xf;
 
-  pub fn store_32(&mut self, address: u32, value: u32) {
-    let address = Bus::translate_address(address);
+    let mut word = 0;
 
-    match address {
-      0x1f80_1080..=0x1f80_10ff => self.dma.write(address, value),
-      _ => self.bus.mem_write_32(address, value)
-    }
-  }
+    for i in 0..4u32 {
+      let word_address = line_base.wrapping_add(i * 4);
+      let fetched = self.bus.mem_read_32(word_address, false);
 
-  // TODO: refactor this into just one method
-  pub fn load_32(&mut self, address: u32) -> (u32, u16) {
-    let previous_cycles = self.synchronize_and_get_current_cycles();
+      self.icache.refill_word(word_address, fetched);
+      self.tick(2);
 
-    let address = Bus::translate_address(address);
+      if word_address == self.pc {
+        word = fetched;
+      }
+    }
 
-    let result = match address {
-      0x1f80_1080..=0x1f80_10ff => self.dma.read(address),
-      _ => self.bus.mem_read_32(address, true)
-    };
+    word
+  }
 
-    let duration = (self.bus.counter.cycles - previous_cycles) as u16;
+  pub fn store_32(&mut self, address: u32, value: u32) {
+    self.write(address, value);
+  }
 
-    (result, duration)
+  pub fn store_16(&mut self, address: u32, value: u16) {
+    self.write(address, value);
   }
 
-  pub fn load_16(&mut self, address: u32) -> (u16, u16) {
-    let previous_cycles = self.synchronize_and_get_current_cycles();
+  pub fn store_8(&mut self, address: u32, value: u8) {
+    self.write(address, value);
+  }
 
-    let result = self.bus.mem_read_16(address);
+  pub fn load_32(&mut self, address: u32) -> (u32, u16) {
+    self.read(address, true, false)
+  }
 
-    let duration = (self.bus.counter.cycles - previous_cycles) as u16;
+  pub fn load_16(&mut self, address: u32) -> (u16, u16) {
+    self.read(address, false, false)
+  }
 
-    (result, duration)
+  pub fn load_8(&mut self, address: u32) -> (u8, u16) {
+    self.read(address, false, false)
   }
 
-  pub fn synchronize_and_get_current_cycles(&mut self) -> i64 {
+  pub fn synchronize_and_get_current_cycles(&mut self, is_lwc: bool) -> i64 {
     self.synchronize_load();
 
     if self.load.is_none() {
-      self.bus.counter.tick(2);
+      self.tick(2);
     }
 
     let previous_cycles = self.bus.counter.cycles;
 
-    // this is the delay to complete the load. TODO: check if command is LWC, as that changes the cycles
-    self.bus.counter.tick(2);
+    // LWC (coprocessor) loads land in a GTE/COP register instead of
+    // contending with the CPU's load-delay slot, so they skip the normal
+    // 2-cycle completion stall a register load pays
+    self.tick(if is_lwc { 1 } else { 2 });
 
     previous_cycles
   }
 
-  pub fn load_8(&mut self, address: u32) -> (u8, u16) {
-    let previous_cycles = self.synchronize_and_get_current_cycles();
-
-    let result = self.bus.mem_read_8(address);
-
-    let duration = (self.bus.counter.cycles - previous_cycles) as u16;
-
-    (result, duration)
-  }
-
   /**
    * TODO: This currently doesn't do anything, but
    * in the future I may refactor the code
@@ -284,6 +412,43 @@ impl CPU {
   }
 
   pub fn tick_instruction(&mut self) {
-    self.bus.counter.tick(1);
+    self.tick(1);
+  }
+
+  // advances both the bus counter and the scheduler by the same delta so the
+  // two clocks never drift apart, then lets any events that just became due fire
+  fn tick(&mut self, cycles: i64) {
+    self.bus.counter.tick(cycles);
+    self.scheduler.advance(cycles as u64);
+
+    if self.scheduler.is_event_ready() {
+      self.run_scheduled_events();
+    }
+  }
+
+  fn run_scheduled_events(&mut self) {
+    for (timestamp, event) in self.scheduler.pop_ready_events() {
+      match event {
+        // reschedule from the timestamp this firing was due at, not from
+        // `self.cycles`: a batch tick that overshoots `next_event` would
+        // otherwise push every future scanline/frame boundary later by the
+        // overshoot, permanently drifting the period
+        EventType::Hblank => {
+          self.scheduler.schedule_at(EventType::Hblank, timestamp + CYCLES_PER_SCANLINE as u64);
+        }
+        EventType::Vblank => {
+          self.scheduler.schedule_at(EventType::Vblank, timestamp + CYCLES_PER_FRAME as u64);
+
+          let mut registers = self.interrupts.get();
+          registers.set_interrupt(Interrupt::Vblank, true);
+          self.interrupts.set(registers);
+        }
+        // DMA channel completion and timer overflow stay driven by their
+        // owning subsystems directly (`DMA::tick`/`DMA::raise_interrupt` run
+        // synchronously out of `step`, and there's no timer subsystem in
+        // this chunk yet); nothing schedules these today
+        EventType::DmaCompletion(_) | EventType::TimerOverflow(_) => {}
+      }
+    }
   }
 }
\ No newline at end of file
@@ -2,7 +2,7 @@ use std::{rc::Rc, cell::Cell};
 
 use self::{dma_interrupt::DmaInterrupt, dma_channel::DmaChannel, dma_channel_control_register::SyncMode};
 
-use super::{counter::{Counter, Device}, bus::Bus, interrupt::interrupt_registers::InterruptRegisters};
+use super::{counter::{Counter, Device}, bus::Bus, interrupt::interrupt_registers::{InterruptRegisters, Interrupt}, savestate::{SaveReader, SaveWriter}};
 
 pub mod dma_interrupt;
 pub mod dma_channel;
@@ -100,7 +100,7 @@ impl DMA {
       } else {
         channel.finish();
 
-        // TODO: interrupts
+        self.raise_interrupt(channel_id);
       }
     }
   }
@@ -148,7 +148,7 @@ impl DMA {
       self.active_count += channel.block_size() as i32;
       channel.finish();
 
-      // TODO interrupts
+      self.raise_interrupt(channel_id);
     }
   }
 
@@ -187,7 +187,7 @@ impl DMA {
 
     if (header & 0xffffff) == 0xffffff {
       channel.finish();
-      // TODO: set interrupt here
+      self.raise_interrupt(channel_id);
     } else {
       channel.gap_ticks += 1;
     }
@@ -197,6 +197,20 @@ impl DMA {
     (self.control & (1 << ((channel_id << 2) + 3))) != 0
   }
 
+  // sets the channel's flag bit if its IRQ is enabled, then raises the shared
+  // DMA interrupt line when the master flag (force/enable/flags) comes up
+  fn raise_interrupt(&mut self, channel_id: usize) {
+    if self.interrupt.channel_enabled(channel_id) {
+      self.interrupt.set_channel_flag(channel_id, true);
+    }
+
+    if self.interrupt.master_flag() {
+      let mut registers = self.interrupts.get();
+      registers.set_interrupt(Interrupt::Dma, true);
+      self.interrupts.set(registers);
+    }
+  }
+
   pub fn tick_gap(&mut self, counter: &mut Counter) {
     let elapsed = counter.sync_and_get_elapsed_cycles(Device::Dma);
 
@@ -257,8 +271,8 @@ impl DMA {
       7 => {
         match minor {
           0 => self.control,
-          4 => self.interrupt.val,
-          6 => self.interrupt.val >> 16,
+          4 => self.interrupt.value(),
+          6 => self.interrupt.value() >> 16,
           _ => panic!("unhandled DMA read at offset {:X}", offset)
         }
       }
@@ -305,7 +319,7 @@ impl DMA {
 
           if channel.word_count == 0 {
             channel.finish();
-            // TODO: interrupts
+            self.raise_interrupt(major as usize);
           }
         }
 
@@ -321,4 +335,40 @@ impl DMA {
       _ => panic!("unhandled DMA write at offset {:X}", offset)
     }
   }
+
+  pub fn save_state(&self, writer: &mut SaveWriter) {
+    writer.write_u32(self.control);
+    writer.write_u32(self.interrupt.val);
+
+    for channel in self.channels {
+      writer.write_u8(channel.channel_id as u8);
+      writer.write_u32(channel.base_address);
+      writer.write_u32(channel.active_address);
+      writer.write_u32(channel.word_count as u32);
+      writer.write_u32(channel.blocks_remaining as u32);
+      writer.write_u32(channel.gap_ticks as u32);
+      writer.write_u32(channel.control.val);
+      writer.write_u32(channel.block_control.val);
+    }
+  }
+
+  pub fn load_state(&mut self, reader: &mut SaveReader) {
+    self.control = reader.read_u32();
+    self.interrupt.val = reader.read_u32();
+
+    for i in 0..self.channels.len() {
+      let channel = &mut self.channels[i];
+
+      // channel_id never changes, it's read back to keep the blob self-describing
+      reader.read_u8();
+
+      channel.base_address = reader.read_u32();
+      channel.active_address = reader.read_u32();
+      channel.word_count = reader.read_u32() as _;
+      channel.blocks_remaining = reader.read_u32() as _;
+      channel.gap_ticks = reader.read_u32() as _;
+      channel.control.val = reader.read_u32();
+      channel.block_control.val = reader.read_u32();
+    }
+  }
 }
\ No newline at end of file
@@ -0,0 +1,70 @@
+// layout of 0x1f8010f4 (DICR):
+//  0-5   unused
+//  6-14  unused
+//  15    force IRQ (bus error, write only, always reads back as set to the master flag)
+//  16-22 channel IRQ enable (one bit per channel)
+//  23    master IRQ enable
+//  24-30 channel IRQ flags (write 1 to acknowledge/clear)
+//  31    master IRQ flag (read-only, derived from the bits above)
+#[derive(Clone, Copy)]
+pub struct DmaInterrupt {
+  pub val: u32
+}
+
+impl DmaInterrupt {
+  pub fn new() -> Self {
+    Self { val: 0 }
+  }
+
+  pub fn force_irq(&self) -> bool {
+    (self.val >> 15) & 0b1 == 1
+  }
+
+  pub fn channel_enabled(&self, channel_id: usize) -> bool {
+    (self.val >> (16 + channel_id)) & 0b1 == 1
+  }
+
+  pub fn master_enabled(&self) -> bool {
+    (self.val >> 23) & 0b1 == 1
+  }
+
+  pub fn channel_flags(&self) -> u8 {
+    ((self.val >> 24) & 0x7f) as u8
+  }
+
+  // per-channel enable bits (16-22), shifted down to line up with
+  // `channel_flags` so the two can be ANDed together bit-for-bit
+  pub fn enabled_mask(&self) -> u8 {
+    ((self.val >> 16) & 0x7f) as u8
+  }
+
+  // re-derived from the *current* enable bits on every read: a channel whose
+  // enable bit gets cleared without acknowledging its flag must drop out of
+  // the master flag immediately, not just at the moment the flag was raised
+  pub fn master_flag(&self) -> bool {
+    self.force_irq() || (self.master_enabled() && (self.channel_flags() & self.enabled_mask()) != 0)
+  }
+
+  pub fn set_channel_flag(&mut self, channel_id: usize, set_active: bool) {
+    if set_active {
+      self.val |= 1 << (24 + channel_id);
+    } else {
+      self.val &= !(1 << (24 + channel_id));
+    }
+  }
+
+  // bits 0-23 (force irq, per-channel enables, master enable) are written
+  // directly. bits 24-30 are write-1-to-acknowledge: a 1 clears the
+  // corresponding flag rather than setting it.
+  pub fn write(&mut self, value: u32) {
+    let acknowledge = (value >> 24) & 0x7f;
+    let flags_after_ack = (self.channel_flags() as u32) & !acknowledge;
+
+    self.val = (value & 0x00ff_ffff) | (flags_after_ack << 24);
+  }
+
+  // bit 31 isn't stored, it's recomputed from the enable/flag bits on every read
+  pub fn value(&self) -> u32 {
+    (self.val & 0x7fff_ffff) | ((self.master_flag() as u32) << 31)
+  }
+}
@@ -0,0 +1,418 @@
+use super::{instruction::Instruction, Cause, CPU};
+
+// the two dispatch tables are generated by build.rs: one 64-entry array
+// indexed by the primary opcode field, one indexed by the SPECIAL funct
+// field. decode collapses to two array lookups and an indirect call instead
+// of a cascading match.
+include!(concat!(env!("OUT_DIR"), "/primary_table.rs"));
+include!(concat!(env!("OUT_DIR"), "/special_table.rs"));
+
+impl CPU {
+  pub fn execute(&mut self, instr: Instruction) {
+    self.execute_load_delay();
+
+    PRIMARY_TABLE[instr.op_code()](self, instr);
+  }
+
+  // commits the result of a previous load-delayed instruction before the
+  // current one gets a chance to overwrite `self.load`
+  pub(super) fn execute_load_delay(&mut self) {
+    if let Some((reg, val, _)) = self.load.take() {
+      self.set_reg(reg, val);
+    }
+  }
+
+  fn branch(&mut self, offset: u32) {
+    self.next_pc = self.pc.wrapping_add(offset << 2);
+    self.branch = true;
+  }
+
+  pub fn op_special(cpu: &mut CPU, instr: Instruction) {
+    SPECIAL_TABLE[instr.funct()](cpu, instr);
+  }
+
+  pub fn op_illegal(cpu: &mut CPU, _instr: Instruction) {
+    cpu.exception(Cause::IllegalInstruction);
+  }
+
+  pub fn op_cop_illegal(cpu: &mut CPU, _instr: Instruction) {
+    cpu.exception(Cause::CoprocessorError);
+  }
+
+  // REGIMM: BLTZ/BGEZ/BLTZAL/BGEZAL, selected by the `rt` field
+  pub fn op_bcond(cpu: &mut CPU, instr: Instruction) {
+    let value = cpu.r[instr.rs()] as i32;
+    let is_bgez = instr.rt() & 0b1 == 1;
+    let should_link = (instr.rt() >> 4) & 0b1 == 1;
+
+    let condition = if is_bgez { value >= 0 } else { value < 0 };
+
+    if should_link {
+      cpu.set_reg(31, cpu.next_pc);
+    }
+
+    if condition {
+      cpu.branch(instr.imm16_se());
+    }
+  }
+
+  pub fn op_j(cpu: &mut CPU, instr: Instruction) {
+    cpu.next_pc = (cpu.pc & 0xf000_0000) | (instr.imm26() << 2);
+    cpu.branch = true;
+  }
+
+  pub fn op_jal(cpu: &mut CPU, instr: Instruction) {
+    cpu.set_reg(31, cpu.next_pc);
+    CPU::op_j(cpu, instr);
+  }
+
+  pub fn op_beq(cpu: &mut CPU, instr: Instruction) {
+    if cpu.r[instr.rs()] == cpu.r[instr.rt()] {
+      cpu.branch(instr.imm16_se());
+    }
+  }
+
+  pub fn op_bne(cpu: &mut CPU, instr: Instruction) {
+    if cpu.r[instr.rs()] != cpu.r[instr.rt()] {
+      cpu.branch(instr.imm16_se());
+    }
+  }
+
+  pub fn op_blez(cpu: &mut CPU, instr: Instruction) {
+    if (cpu.r[instr.rs()] as i32) <= 0 {
+      cpu.branch(instr.imm16_se());
+    }
+  }
+
+  pub fn op_bgtz(cpu: &mut CPU, instr: Instruction) {
+    if (cpu.r[instr.rs()] as i32) > 0 {
+      cpu.branch(instr.imm16_se());
+    }
+  }
+
+  pub fn op_addi(cpu: &mut CPU, instr: Instruction) {
+    let rs = cpu.r[instr.rs()] as i32;
+
+    match rs.checked_add(instr.imm16_se() as i32) {
+      Some(result) => cpu.set_reg(instr.rt(), result as u32),
+      None => cpu.exception(Cause::Overflow)
+    }
+  }
+
+  pub fn op_addiu(cpu: &mut CPU, instr: Instruction) {
+    let result = cpu.r[instr.rs()].wrapping_add(instr.imm16_se());
+    cpu.set_reg(instr.rt(), result);
+  }
+
+  pub fn op_slti(cpu: &mut CPU, instr: Instruction) {
+    let result = (cpu.r[instr.rs()] as i32) < (instr.imm16_se() as i32);
+    cpu.set_reg(instr.rt(), result as u32);
+  }
+
+  pub fn op_sltiu(cpu: &mut CPU, instr: Instruction) {
+    let result = cpu.r[instr.rs()] < instr.imm16_se();
+    cpu.set_reg(instr.rt(), result as u32);
+  }
+
+  pub fn op_andi(cpu: &mut CPU, instr: Instruction) {
+    let result = cpu.r[instr.rs()] & (instr.imm16() as u32);
+    cpu.set_reg(instr.rt(), result);
+  }
+
+  pub fn op_ori(cpu: &mut CPU, instr: Instruction) {
+    let result = cpu.r[instr.rs()] | (instr.imm16() as u32);
+    cpu.set_reg(instr.rt(), result);
+  }
+
+  pub fn op_xori(cpu: &mut CPU, instr: Instruction) {
+    let result = cpu.r[instr.rs()] ^ (instr.imm16() as u32);
+    cpu.set_reg(instr.rt(), result);
+  }
+
+  pub fn op_lui(cpu: &mut CPU, instr: Instruction) {
+    cpu.set_reg(instr.rt(), (instr.imm16() as u32) << 16);
+  }
+
+  pub fn op_cop0(cpu: &mut CPU, instr: Instruction) {
+    match instr.cop_opcode() {
+      0x00 => CPU::op_mfc0(cpu, instr),
+      0x04 => CPU::op_mtc0(cpu, instr),
+      0x10 => CPU::op_rfe(cpu, instr),
+      n => panic!("unhandled cop0 instruction {n:#x}")
+    }
+  }
+
+  fn op_mfc0(cpu: &mut CPU, instr: Instruction) {
+    let value = match instr.rd() {
+      12 => cpu.cop0.sr,
+      13 => cpu.cop0.cause,
+      14 => cpu.cop0.epc,
+      n => panic!("unhandled read from cop0 register {n}")
+    };
+
+    cpu.load = Some((instr.rt(), value, 0));
+  }
+
+  fn op_mtc0(cpu: &mut CPU, instr: Instruction) {
+    let value = cpu.r[instr.rt()];
+
+    match instr.rd() {
+      12 => cpu.cop0.sr = value,
+      13 => cpu.cop0.cause = (cpu.cop0.cause & !0x300) | (value & 0x300),
+      14 => cpu.cop0.epc = value,
+      _ => {}
+    }
+  }
+
+  fn op_rfe(cpu: &mut CPU, _instr: Instruction) {
+    cpu.cop0.return_from_exception();
+  }
+
+  // the GTE (COP2) isn't emulated in this chunk yet
+  pub fn op_cop2(cpu: &mut CPU, _instr: Instruction) {
+    let _ = cpu;
+    todo!("GTE instructions are not implemented yet");
+  }
+
+  pub fn op_lwc2(cpu: &mut CPU, _instr: Instruction) {
+    let _ = cpu;
+    todo!("GTE data loads are not implemented yet");
+  }
+
+  pub fn op_swc2(cpu: &mut CPU, _instr: Instruction) {
+    let _ = cpu;
+    todo!("GTE data stores are not implemented yet");
+  }
+
+  pub fn op_lb(cpu: &mut CPU, instr: Instruction) {
+    let address = cpu.r[instr.rs()].wrapping_add(instr.imm16_se());
+    let (value, duration) = cpu.load_8(address);
+
+    cpu.load = Some((instr.rt(), (value as i8) as u32, duration));
+  }
+
+  pub fn op_lbu(cpu: &mut CPU, instr: Instruction) {
+    let address = cpu.r[instr.rs()].wrapping_add(instr.imm16_se());
+    let (value, duration) = cpu.load_8(address);
+
+    cpu.load = Some((instr.rt(), value as u32, duration));
+  }
+
+  pub fn op_lh(cpu: &mut CPU, instr: Instruction) {
+    let address = cpu.r[instr.rs()].wrapping_add(instr.imm16_se());
+    let (value, duration) = cpu.load_16(address);
+
+    cpu.load = Some((instr.rt(), (value as i16) as u32, duration));
+  }
+
+  pub fn op_lhu(cpu: &mut CPU, instr: Instruction) {
+    let address = cpu.r[instr.rs()].wrapping_add(instr.imm16_se());
+    let (value, duration) = cpu.load_16(address);
+
+    cpu.load = Some((instr.rt(), value as u32, duration));
+  }
+
+  pub fn op_lw(cpu: &mut CPU, instr: Instruction) {
+    let address = cpu.r[instr.rs()].wrapping_add(instr.imm16_se());
+    let (value, duration) = cpu.load_32(address);
+
+    cpu.load = Some((instr.rt(), value, duration));
+  }
+
+  pub fn op_lwl(_cpu: &mut CPU, _instr: Instruction) {
+    todo!("unaligned loads are not implemented yet");
+  }
+
+  pub fn op_lwr(_cpu: &mut CPU, _instr: Instruction) {
+    todo!("unaligned loads are not implemented yet");
+  }
+
+  pub fn op_sb(cpu: &mut CPU, instr: Instruction) {
+    let address = cpu.r[instr.rs()].wrapping_add(instr.imm16_se());
+    cpu.store_8(address, cpu.r[instr.rt()] as u8);
+  }
+
+  pub fn op_sh(cpu: &mut CPU, instr: Instruction) {
+    let address = cpu.r[instr.rs()].wrapping_add(instr.imm16_se());
+    cpu.store_16(address, cpu.r[instr.rt()] as u16);
+  }
+
+  pub fn op_sw(cpu: &mut CPU, instr: Instruction) {
+    let address = cpu.r[instr.rs()].wrapping_add(instr.imm16_se());
+    cpu.store_32(address, cpu.r[instr.rt()]);
+  }
+
+  pub fn op_swl(_cpu: &mut CPU, _instr: Instruction) {
+    todo!("unaligned stores are not implemented yet");
+  }
+
+  pub fn op_swr(_cpu: &mut CPU, _instr: Instruction) {
+    todo!("unaligned stores are not implemented yet");
+  }
+
+  pub fn op_sll(cpu: &mut CPU, instr: Instruction) {
+    cpu.set_reg(instr.rd(), cpu.r[instr.rt()] << instr.shift());
+  }
+
+  pub fn op_srl(cpu: &mut CPU, instr: Instruction) {
+    cpu.set_reg(instr.rd(), cpu.r[instr.rt()] >> instr.shift());
+  }
+
+  pub fn op_sra(cpu: &mut CPU, instr: Instruction) {
+    let result = (cpu.r[instr.rt()] as i32) >> instr.shift();
+    cpu.set_reg(instr.rd(), result as u32);
+  }
+
+  pub fn op_sllv(cpu: &mut CPU, instr: Instruction) {
+    let shift = cpu.r[instr.rs()] & 0x1f;
+    cpu.set_reg(instr.rd(), cpu.r[instr.rt()] << shift);
+  }
+
+  pub fn op_srlv(cpu: &mut CPU, instr: Instruction) {
+    let shift = cpu.r[instr.rs()] & 0x1f;
+    cpu.set_reg(instr.rd(), cpu.r[instr.rt()] >> shift);
+  }
+
+  pub fn op_srav(cpu: &mut CPU, instr: Instruction) {
+    let shift = cpu.r[instr.rs()] & 0x1f;
+    let result = (cpu.r[instr.rt()] as i32) >> shift;
+    cpu.set_reg(instr.rd(), result as u32);
+  }
+
+  pub fn op_jr(cpu: &mut CPU, instr: Instruction) {
+    cpu.next_pc = cpu.r[instr.rs()];
+    cpu.branch = true;
+  }
+
+  pub fn op_jalr(cpu: &mut CPU, instr: Instruction) {
+    let return_address = cpu.next_pc;
+
+    cpu.next_pc = cpu.r[instr.rs()];
+    cpu.branch = true;
+
+    cpu.set_reg(instr.rd(), return_address);
+  }
+
+  pub fn op_syscall(cpu: &mut CPU, _instr: Instruction) {
+    cpu.exception(Cause::SysCall);
+  }
+
+  pub fn op_break(cpu: &mut CPU, _instr: Instruction) {
+    cpu.exception(Cause::Break);
+  }
+
+  pub fn op_mfhi(cpu: &mut CPU, instr: Instruction) {
+    cpu.set_reg(instr.rd(), cpu.hi);
+  }
+
+  pub fn op_mthi(cpu: &mut CPU, instr: Instruction) {
+    cpu.hi = cpu.r[instr.rs()];
+  }
+
+  pub fn op_mflo(cpu: &mut CPU, instr: Instruction) {
+    cpu.set_reg(instr.rd(), cpu.low);
+  }
+
+  pub fn op_mtlo(cpu: &mut CPU, instr: Instruction) {
+    cpu.low = cpu.r[instr.rs()];
+  }
+
+  pub fn op_mult(cpu: &mut CPU, instr: Instruction) {
+    let result = (cpu.r[instr.rs()] as i32 as i64).wrapping_mul(cpu.r[instr.rt()] as i32 as i64) as u64;
+
+    cpu.hi = (result >> 32) as u32;
+    cpu.low = result as u32;
+  }
+
+  pub fn op_multu(cpu: &mut CPU, instr: Instruction) {
+    let result = (cpu.r[instr.rs()] as u64).wrapping_mul(cpu.r[instr.rt()] as u64);
+
+    cpu.hi = (result >> 32) as u32;
+    cpu.low = result as u32;
+  }
+
+  pub fn op_div(cpu: &mut CPU, instr: Instruction) {
+    let numerator = cpu.r[instr.rs()] as i32;
+    let denominator = cpu.r[instr.rt()] as i32;
+
+    if denominator == 0 {
+      cpu.hi = numerator as u32;
+      cpu.low = if numerator >= 0 { 0xffff_ffff } else { 1 };
+    } else if numerator as u32 == 0x8000_0000 && denominator == -1 {
+      cpu.hi = 0;
+      cpu.low = 0x8000_0000;
+    } else {
+      cpu.hi = (numerator % denominator) as u32;
+      cpu.low = (numerator / denominator) as u32;
+    }
+  }
+
+  pub fn op_divu(cpu: &mut CPU, instr: Instruction) {
+    let numerator = cpu.r[instr.rs()];
+    let denominator = cpu.r[instr.rt()];
+
+    if denominator == 0 {
+      cpu.hi = numerator;
+      cpu.low = 0xffff_ffff;
+    } else {
+      cpu.hi = numerator % denominator;
+      cpu.low = numerator / denominator;
+    }
+  }
+
+  pub fn op_add(cpu: &mut CPU, instr: Instruction) {
+    let rs = cpu.r[instr.rs()] as i32;
+    let rt = cpu.r[instr.rt()] as i32;
+
+    match rs.checked_add(rt) {
+      Some(result) => cpu.set_reg(instr.rd(), result as u32),
+      None => cpu.exception(Cause::Overflow)
+    }
+  }
+
+  pub fn op_addu(cpu: &mut CPU, instr: Instruction) {
+    let result = cpu.r[instr.rs()].wrapping_add(cpu.r[instr.rt()]);
+    cpu.set_reg(instr.rd(), result);
+  }
+
+  pub fn op_sub(cpu: &mut CPU, instr: Instruction) {
+    let rs = cpu.r[instr.rs()] as i32;
+    let rt = cpu.r[instr.rt()] as i32;
+
+    match rs.checked_sub(rt) {
+      Some(result) => cpu.set_reg(instr.rd(), result as u32),
+      None => cpu.exception(Cause::Overflow)
+    }
+  }
+
+  pub fn op_subu(cpu: &mut CPU, instr: Instruction) {
+    let result = cpu.r[instr.rs()].wrapping_sub(cpu.r[instr.rt()]);
+    cpu.set_reg(instr.rd(), result);
+  }
+
+  pub fn op_and(cpu: &mut CPU, instr: Instruction) {
+    cpu.set_reg(instr.rd(), cpu.r[instr.rs()] & cpu.r[instr.rt()]);
+  }
+
+  pub fn op_or(cpu: &mut CPU, instr: Instruction) {
+    cpu.set_reg(instr.rd(), cpu.r[instr.rs()] | cpu.r[instr.rt()]);
+  }
+
+  pub fn op_xor(cpu: &mut CPU, instr: Instruction) {
+    cpu.set_reg(instr.rd(), cpu.r[instr.rs()] ^ cpu.r[instr.rt()]);
+  }
+
+  pub fn op_nor(cpu: &mut CPU, instr: Instruction) {
+    cpu.set_reg(instr.rd(), !(cpu.r[instr.rs()] | cpu.r[instr.rt()]));
+  }
+
+  pub fn op_slt(cpu: &mut CPU, instr: Instruction) {
+    let result = (cpu.r[instr.rs()] as i32) < (cpu.r[instr.rt()] as i32);
+    cpu.set_reg(instr.rd(), result as u32);
+  }
+
+  pub fn op_sltu(cpu: &mut CPU, instr: Instruction) {
+    let result = cpu.r[instr.rs()] < cpu.r[instr.rt()];
+    cpu.set_reg(instr.rd(), result as u32);
+  }
+}
@@ -0,0 +1,79 @@
+// R3000A instruction cache: 256 lines of 4 words each, with a tag and a
+// per-word valid bit per line (the real chip can have a valid miss mid-line
+// if only part of it has been filled since the last invalidation)
+const NUM_LINES: usize = 256;
+const WORDS_PER_LINE: usize = 4;
+
+#[derive(Clone, Copy)]
+struct CacheLine {
+  tag: u32,
+  valid: [bool; WORDS_PER_LINE],
+  data: [u32; WORDS_PER_LINE]
+}
+
+impl CacheLine {
+  fn new() -> Self {
+    Self {
+      tag: 0,
+      valid: [false; WORDS_PER_LINE],
+      data: [0; WORDS_PER_LINE]
+    }
+  }
+}
+
+pub struct ICache {
+  lines: Vec<CacheLine>
+}
+
+impl ICache {
+  pub fn new() -> Self {
+    Self {
+      lines: vec![CacheLine::new(); NUM_LINES]
+    }
+  }
+
+  fn line_index(address: u32) -> usize {
+    ((address >> 4) & 0xff) as usize
+  }
+
+  fn word_index(address: u32) -> usize {
+    ((address >> 2) & 0b11) as usize
+  }
+
+  fn tag(address: u32) -> u32 {
+    address & !0xf
+  }
+
+  pub fn lookup(&self, address: u32) -> Option<u32> {
+    let line = &self.lines[Self::line_index(address)];
+    let word = Self::word_index(address);
+
+    if line.tag == Self::tag(address) && line.valid[word] {
+      Some(line.data[word])
+    } else {
+      None
+    }
+  }
+
+  // fills a single word of a line; switching to a new tag invalidates the
+  // rest of the line so a partially-filled line can never return stale data
+  pub fn refill_word(&mut self, address: u32, value: u32) {
+    let index = Self::line_index(address);
+    let tag = Self::tag(address);
+    let word = Self::word_index(address);
+
+    let line = &mut self.lines[index];
+
+    if line.tag != tag {
+      line.tag = tag;
+      line.valid = [false; WORDS_PER_LINE];
+    }
+
+    line.data[word] = value;
+    line.valid[word] = true;
+  }
+
+  pub fn invalidate_line(&mut self, address: u32) {
+    self.lines[Self::line_index(address)].valid = [false; WORDS_PER_LINE];
+  }
+}
@@ -0,0 +1,63 @@
+// thin wrapper around a raw 32-bit MIPS-I word; all the field accessors are
+// just bit slices out of the underlying encoding, see the R3000A manual for
+// the primary-opcode / SPECIAL-funct layout
+#[derive(Clone, Copy)]
+pub struct Instruction(pub u32);
+
+impl Instruction {
+  pub fn new(instr: u32) -> Self {
+    Self(instr)
+  }
+
+  pub fn op_code(&self) -> usize {
+    (self.0 >> 26) as usize
+  }
+
+  pub fn funct(&self) -> usize {
+    (self.0 & 0x3f) as usize
+  }
+
+  pub fn rs(&self) -> usize {
+    ((self.0 >> 21) & 0x1f) as usize
+  }
+
+  pub fn rt(&self) -> usize {
+    ((self.0 >> 16) & 0x1f) as usize
+  }
+
+  pub fn rd(&self) -> usize {
+    ((self.0 >> 11) & 0x1f) as usize
+  }
+
+  pub fn shift(&self) -> u32 {
+    (self.0 >> 6) & 0x1f
+  }
+
+  pub fn imm16(&self) -> u16 {
+    (self.0 & 0xffff) as u16
+  }
+
+  pub fn imm16_se(&self) -> u32 {
+    (self.0 as i16) as u32
+  }
+
+  pub fn imm26(&self) -> u32 {
+    self.0 & 0x3ff_ffff
+  }
+
+  // COP0/COP2 instructions are decoded like SPECIAL: the coprocessor opcode
+  // (MFCn/MTCn/...) lives in the `rs` field
+  pub fn cop_opcode(&self) -> usize {
+    self.rs()
+  }
+}
+
+// human-readable mnemonic for each dispatch slot, only compiled in when
+// tracing/disassembly is needed so release builds don't pay for the strings.
+// gated on `debug_assertions` rather than a Cargo feature since there's no
+// manifest in this tree to declare one in
+#[cfg(debug_assertions)]
+pub mod mnemonics {
+  pub const PRIMARY: [&str; 64] = include!(concat!(env!("OUT_DIR"), "/primary_mnemonics.rs"));
+  pub const SPECIAL: [&str; 64] = include!(concat!(env!("OUT_DIR"), "/special_mnemonics.rs"));
+}
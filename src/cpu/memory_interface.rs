@@ -0,0 +1,99 @@
+use super::{bus::Bus, Cause, CPU};
+
+// lets `MemoryInterface::read`/`write` be generic over access width instead
+// of CPU needing one near-identical method per width
+pub trait MemVal: Copy {
+  const WIDTH: u32;
+
+  fn from_u32(val: u32) -> Self;
+  fn as_u32(self) -> u32;
+}
+
+impl MemVal for u8 {
+  const WIDTH: u32 = 1;
+
+  fn from_u32(val: u32) -> Self { val as u8 }
+  fn as_u32(self) -> u32 { self as u32 }
+}
+
+impl MemVal for u16 {
+  const WIDTH: u32 = 2;
+
+  fn from_u32(val: u32) -> Self { val as u16 }
+  fn as_u32(self) -> u32 { self as u32 }
+}
+
+impl MemVal for u32 {
+  const WIDTH: u32 = 4;
+
+  fn from_u32(val: u32) -> Self { val }
+  fn as_u32(self) -> u32 { self }
+}
+
+pub trait MemoryInterface {
+  // `is_data` distinguishes a genuine CPU data access from an instruction
+  // fetch (only `Bus::mem_read_32` cares); `is_lwc` marks an LWC-style
+  // coprocessor load, which lands in a GTE/COP register instead of
+  // contending with the CPU's load-delay slot and so completes faster
+  fn read<T: MemVal>(&mut self, address: u32, is_data: bool, is_lwc: bool) -> (T, u16);
+  fn write<T: MemVal>(&mut self, address: u32, value: T);
+}
+
+impl MemoryInterface for CPU {
+  fn read<T: MemVal>(&mut self, address: u32, is_data: bool, is_lwc: bool) -> (T, u16) {
+    if address & (T::WIDTH - 1) != 0 {
+      self.exception(Cause::LoadAddressError);
+      return (T::from_u32(0), 0);
+    }
+
+    self.check_watchpoint(address, super::WatchKind::Read);
+
+    let previous_cycles = self.synchronize_and_get_current_cycles(is_lwc);
+
+    let translated = Bus::translate_address(address);
+
+    let value = match (translated, T::WIDTH) {
+      (0x1f80_1080..=0x1f80_10ff, 4) => self.dma.read(translated),
+      (_, 4) => self.bus.mem_read_32(translated, is_data),
+      (_, 2) => self.bus.mem_read_16(translated) as u32,
+      (_, _) => self.bus.mem_read_8(translated) as u32
+    };
+
+    let duration = (self.bus.counter.cycles - previous_cycles) as u16;
+
+    (T::from_u32(value), duration)
+  }
+
+  fn write<T: MemVal>(&mut self, address: u32, value: T) {
+    if address & (T::WIDTH - 1) != 0 {
+      self.exception(Cause::StoreAddressError);
+      return;
+    }
+
+    self.check_watchpoint(address, super::WatchKind::Write);
+
+    // while the cache is isolated, writes land in the cache line instead of
+    // RAM regardless of width: this is how the BIOS flushes/invalidates the
+    // I-cache. Swc (SR bit 17) picks whether that hit fills the line with
+    // the written word or just invalidates it, used by the BIOS's bulk
+    // cache-clear routine
+    if T::WIDTH == 4 && !self.cop0.is_cache_disabled() {
+      if self.cop0.is_cache_invalidate_mode() {
+        self.icache.invalidate_line(address & !0b11);
+      } else {
+        self.icache.refill_word(address & !0b11, value.as_u32());
+      }
+
+      return;
+    }
+
+    let translated = Bus::translate_address(address);
+
+    match (translated, T::WIDTH) {
+      (0x1f80_1080..=0x1f80_10ff, 4) => self.dma.write(translated, value.as_u32()),
+      (_, 4) => self.bus.mem_write_32(translated, value.as_u32()),
+      (_, 2) => self.bus.mem_write_16(translated, value.as_u32() as u16),
+      (_, _) => self.bus.mem_write_8(translated, value.as_u32() as u8)
+    }
+  }
+}
@@ -0,0 +1,223 @@
+use std::{fs, io, path::{Path, PathBuf}};
+
+use super::CPU;
+
+pub const SAVESTATE_MAGIC: u32 = 0x31535350; // "PSS1"
+pub const SAVESTATE_VERSION: u32 = 1;
+pub const SAVESTATE_EXTENSION: &str = "state";
+
+#[derive(Debug)]
+pub enum SaveStateError {
+  InvalidMagic,
+  UnsupportedVersion(u32)
+}
+
+pub struct SaveWriter {
+  buf: Vec<u8>
+}
+
+impl SaveWriter {
+  pub fn new() -> Self {
+    let mut writer = Self { buf: Vec::new() };
+
+    writer.write_u32(SAVESTATE_MAGIC);
+    writer.write_u32(SAVESTATE_VERSION);
+
+    writer
+  }
+
+  pub fn write_u8(&mut self, val: u8) {
+    self.buf.push(val);
+  }
+
+  pub fn write_u16(&mut self, val: u16) {
+    self.buf.extend_from_slice(&val.to_le_bytes());
+  }
+
+  pub fn write_u32(&mut self, val: u32) {
+    self.buf.extend_from_slice(&val.to_le_bytes());
+  }
+
+  pub fn write_u64(&mut self, val: u64) {
+    self.buf.extend_from_slice(&val.to_le_bytes());
+  }
+
+  pub fn write_bool(&mut self, val: bool) {
+    self.write_u8(val as u8);
+  }
+
+  pub fn into_vec(self) -> Vec<u8> {
+    self.buf
+  }
+}
+
+pub struct SaveReader<'a> {
+  data: &'a [u8],
+  pos: usize
+}
+
+impl<'a> SaveReader<'a> {
+  pub fn new(data: &'a [u8]) -> Result<Self, SaveStateError> {
+    let mut reader = Self { data, pos: 0 };
+
+    if reader.read_u32() != SAVESTATE_MAGIC {
+      return Err(SaveStateError::InvalidMagic);
+    }
+
+    let version = reader.read_u32();
+
+    if version != SAVESTATE_VERSION {
+      return Err(SaveStateError::UnsupportedVersion(version));
+    }
+
+    Ok(reader)
+  }
+
+  pub fn read_u8(&mut self) -> u8 {
+    let val = self.data[self.pos];
+    self.pos += 1;
+    val
+  }
+
+  pub fn read_u16(&mut self) -> u16 {
+    let val = u16::from_le_bytes(self.data[self.pos..self.pos + 2].try_into().unwrap());
+    self.pos += 2;
+    val
+  }
+
+  pub fn read_u32(&mut self) -> u32 {
+    let val = u32::from_le_bytes(self.data[self.pos..self.pos + 4].try_into().unwrap());
+    self.pos += 4;
+    val
+  }
+
+  pub fn read_u64(&mut self) -> u64 {
+    let val = u64::from_le_bytes(self.data[self.pos..self.pos + 8].try_into().unwrap());
+    self.pos += 8;
+    val
+  }
+
+  pub fn read_bool(&mut self) -> bool {
+    self.read_u8() != 0
+  }
+}
+
+impl CPU {
+  pub fn save_state(&self) -> Vec<u8> {
+    let mut writer = SaveWriter::new();
+
+    writer.write_u32(self.pc);
+    writer.write_u32(self.next_pc);
+    writer.write_u32(self.current_pc);
+
+    for reg in self.r {
+      writer.write_u32(reg);
+    }
+
+    writer.write_u32(self.hi);
+    writer.write_u32(self.low);
+
+    writer.write_u32(self.cop0.sr);
+    writer.write_u32(self.cop0.cause);
+    writer.write_u32(self.cop0.epc);
+
+    writer.write_bool(self.branch);
+    writer.write_bool(self.delay_slot);
+
+    match self.load {
+      Some((reg, val, cycles)) => {
+        writer.write_bool(true);
+        writer.write_u32(reg as u32);
+        writer.write_u32(val);
+        writer.write_u16(cycles);
+      }
+      None => writer.write_bool(false)
+    }
+
+    self.dma.save_state(&mut writer);
+    self.bus.save_state(&mut writer);
+    self.scheduler.save_state(&mut writer);
+
+    writer.into_vec()
+  }
+
+  pub fn load_state(&mut self, data: &[u8]) -> Result<(), SaveStateError> {
+    let mut reader = SaveReader::new(data)?;
+
+    self.pc = reader.read_u32();
+    self.next_pc = reader.read_u32();
+    self.current_pc = reader.read_u32();
+
+    for reg in self.r.iter_mut() {
+      *reg = reader.read_u32();
+    }
+
+    self.hi = reader.read_u32();
+    self.low = reader.read_u32();
+
+    self.cop0.sr = reader.read_u32();
+    self.cop0.cause = reader.read_u32();
+    self.cop0.epc = reader.read_u32();
+
+    self.branch = reader.read_bool();
+    self.delay_slot = reader.read_bool();
+
+    self.load = if reader.read_bool() {
+      let reg = reader.read_u32() as usize;
+      let val = reader.read_u32();
+      let cycles = reader.read_u16();
+
+      Some((reg, val, cycles))
+    } else {
+      None
+    };
+
+    self.dma.load_state(&mut reader);
+    self.bus.load_state(&mut reader);
+    self.scheduler.load_state(&mut reader);
+
+    Ok(())
+  }
+}
+
+fn slot_path(directory: &Path, slot: u32) -> PathBuf {
+  directory.join(format!("slot{slot}.{SAVESTATE_EXTENSION}"))
+}
+
+pub fn save_to_slot(cpu: &CPU, directory: &Path, slot: u32) -> io::Result<()> {
+  fs::create_dir_all(directory)?;
+
+  fs::write(slot_path(directory, slot), cpu.save_state())
+}
+
+pub fn load_from_slot(cpu: &mut CPU, directory: &Path, slot: u32) -> io::Result<()> {
+  let data = fs::read(slot_path(directory, slot))?;
+
+  cpu.load_state(&data).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, format!("{err:?}")))
+}
+
+// resolves the most recently written slot by file modification time rather
+// than by parsing slot numbers out of the filename, so a quick-load always
+// grabs the latest snapshot even if slots were written out of order
+pub fn load_most_recent(cpu: &mut CPU, directory: &Path) -> io::Result<()> {
+  let mut newest: Option<(std::time::SystemTime, PathBuf)> = None;
+
+  for entry in fs::read_dir(directory)? {
+    let entry = entry?;
+    let path = entry.path();
+
+    if path.extension().map_or(false, |ext| ext == SAVESTATE_EXTENSION) {
+      let modified = entry.metadata()?.modified()?;
+
+      if newest.as_ref().map_or(true, |(time, _)| modified > *time) {
+        newest = Some((modified, path));
+      }
+    }
+  }
+
+  let (_, path) = newest.ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no savestate slots found"))?;
+
+  let data = fs::read(path)?;
+
+  cpu.load_state(&data).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, format!("{err:?}")))
+}
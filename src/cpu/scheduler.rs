@@ -0,0 +1,166 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use super::{CYCLES_PER_SCANLINE, CYCLES_PER_FRAME};
+use super::savestate::{SaveReader, SaveWriter};
+
+// cycle timestamps are tracked as a monotonically increasing u64 so the
+// scheduler never has to worry about the i64 cycle counter wrapping
+//
+// `DmaCompletion`/`TimerOverflow` are reserved for when DMA channel
+// completion and timer overflow move off their current per-cycle polling
+// (`DMA::tick`/`DMA::raise_interrupt` run synchronously out of `CPU::step`,
+// and there's no timer/counter subsystem in this chunk yet to drive from
+// here) onto the scheduler; nothing schedules them today
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EventType {
+  Hblank,
+  Vblank,
+  DmaCompletion(usize),
+  TimerOverflow(usize)
+}
+
+#[derive(Clone, Copy, Eq, PartialEq)]
+struct ScheduledEvent {
+  timestamp: u64,
+  event: EventType
+}
+
+// reverse the ordering so a std `BinaryHeap` (a max-heap) behaves like a min-heap
+// keyed on the soonest timestamp
+impl Ord for ScheduledEvent {
+  fn cmp(&self, other: &Self) -> Ordering {
+    other.timestamp.cmp(&self.timestamp)
+  }
+}
+
+impl PartialOrd for ScheduledEvent {
+  fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+pub struct Scheduler {
+  cycles: u64,
+  next_event: u64,
+  events: BinaryHeap<ScheduledEvent>
+}
+
+impl Scheduler {
+  pub fn new() -> Self {
+    let mut scheduler = Self {
+      cycles: 0,
+      next_event: u64::MAX,
+      events: BinaryHeap::new()
+    };
+
+    scheduler.schedule(EventType::Hblank, CYCLES_PER_SCANLINE as u64);
+    scheduler.schedule(EventType::Vblank, CYCLES_PER_FRAME as u64);
+
+    scheduler
+  }
+
+  pub fn cycles(&self) -> u64 {
+    self.cycles
+  }
+
+  pub fn schedule(&mut self, event: EventType, cycles_from_now: u64) {
+    self.schedule_at(event, self.cycles + cycles_from_now);
+  }
+
+  // schedules relative to an absolute timestamp rather than "now". a
+  // self-rescheduling event must use this with its own due timestamp
+  // (not `schedule`, which is relative to whatever `self.cycles` happens to
+  // be when the handler runs) or a batch tick that overshoots `next_event`
+  // permanently drifts the period forward every time it fires
+  pub fn schedule_at(&mut self, event: EventType, timestamp: u64) {
+    self.events.push(ScheduledEvent { timestamp, event });
+
+    if timestamp < self.next_event {
+      self.next_event = timestamp;
+    }
+  }
+
+  // advances the scheduler's clock; callers pass in the same delta they tick
+  // the bus counter with, so the two clocks never drift apart
+  pub fn advance(&mut self, cycles: u64) {
+    self.cycles += cycles;
+  }
+
+  // lets the hot loop compare against a single cached timestamp instead of
+  // scanning the heap on every instruction
+  pub fn is_event_ready(&self) -> bool {
+    self.cycles >= self.next_event
+  }
+
+  pub fn next_event_timestamp(&self) -> u64 {
+    self.next_event
+  }
+
+  // returns each ready event alongside the timestamp it was due at, so a
+  // handler that reschedules itself can do so relative to that timestamp
+  // instead of `self.cycles` (which may already be past it)
+  pub fn pop_ready_events(&mut self) -> Vec<(u64, EventType)> {
+    let mut ready = Vec::new();
+
+    while let Some(event) = self.events.peek() {
+      if event.timestamp <= self.cycles {
+        let event = self.events.pop().unwrap();
+        ready.push((event.timestamp, event.event));
+      } else {
+        break;
+      }
+    }
+
+    self.next_event = self.events.peek().map_or(u64::MAX, |event| event.timestamp);
+
+    ready
+  }
+
+  pub fn save_state(&self, writer: &mut SaveWriter) {
+    writer.write_u64(self.cycles);
+
+    writer.write_u32(self.events.len() as u32);
+
+    for scheduled in self.events.iter() {
+      writer.write_u64(scheduled.timestamp);
+
+      match scheduled.event {
+        EventType::Hblank => writer.write_u8(0),
+        EventType::Vblank => writer.write_u8(1),
+        EventType::DmaCompletion(channel) => {
+          writer.write_u8(2);
+          writer.write_u8(channel as u8);
+        }
+        EventType::TimerOverflow(timer) => {
+          writer.write_u8(3);
+          writer.write_u8(timer as u8);
+        }
+      }
+    }
+  }
+
+  pub fn load_state(&mut self, reader: &mut SaveReader) {
+    self.cycles = reader.read_u64();
+
+    let count = reader.read_u32();
+
+    self.events.clear();
+
+    for _ in 0..count {
+      let timestamp = reader.read_u64();
+
+      let event = match reader.read_u8() {
+        0 => EventType::Hblank,
+        1 => EventType::Vblank,
+        2 => EventType::DmaCompletion(reader.read_u8() as usize),
+        3 => EventType::TimerOverflow(reader.read_u8() as usize),
+        n => panic!("unknown scheduled event tag {n}")
+      };
+
+      self.events.push(ScheduledEvent { timestamp, event });
+    }
+
+    self.next_event = self.events.peek().map_or(u64::MAX, |event| event.timestamp);
+  }
+}
@@ -0,0 +1,109 @@
+use crate::cpu::{StopReason, CPU};
+
+// turns the commented-out trace println! in `CPU::step` into a real,
+// always-available inspection subsystem: breakpoints on `current_pc`,
+// read/write watchpoints, single-stepping and register/memory inspection
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Command {
+  Step,
+  StepN(u32),
+  Continue,
+  Registers,
+  MemoryDump(u32, u32)
+}
+
+pub struct Debugger {
+  last_command: Option<Command>,
+  repeat_count: u32,
+  trace_only: bool
+}
+
+impl Debugger {
+  pub fn new() -> Self {
+    Self {
+      last_command: None,
+      repeat_count: 0,
+      trace_only: false
+    }
+  }
+
+  pub fn set_trace_only(&mut self, trace_only: bool) {
+    self.trace_only = trace_only;
+  }
+
+  pub fn last_command(&self) -> Option<Command> {
+    self.last_command
+  }
+
+  pub fn repeat_count(&self) -> u32 {
+    self.repeat_count
+  }
+
+  // runs a single instruction and reports why it stopped, if at all
+  pub fn step(&mut self, cpu: &mut CPU) -> Option<StopReason> {
+    self.last_command = Some(Command::Step);
+
+    cpu.step();
+
+    cpu.take_stop_reason()
+  }
+
+  // repeats the last step `count` times, used for "step N" / repeat commands
+  pub fn step_n(&mut self, cpu: &mut CPU, count: u32) -> Option<StopReason> {
+    self.last_command = Some(Command::StepN(count));
+    self.repeat_count = count;
+
+    // only the first step might need to resume past a breakpoint we're
+    // currently paused at; `CPU::step` consumes the suppression after a
+    // single use, so later iterations check breakpoints normally instead of
+    // silently stepping through every one of them
+    cpu.resume_past_breakpoint();
+
+    for _ in 0..count {
+      cpu.step();
+
+      if let Some(reason) = cpu.take_stop_reason() {
+        return Some(reason);
+      }
+    }
+
+    None
+  }
+
+  // runs until a breakpoint/watchpoint trips. in `trace_only` mode hits are
+  // logged by the caller but don't actually halt execution
+  pub fn run_until_stop(&mut self, cpu: &mut CPU) -> StopReason {
+    self.last_command = Some(Command::Continue);
+
+    loop {
+      cpu.step();
+
+      if let Some(reason) = cpu.take_stop_reason() {
+        if !self.trace_only {
+          return reason;
+        }
+
+        // only a breakpoint trip needs to resume past itself; doing this
+        // unconditionally would also suppress the very next breakpoint check
+        // after a watchpoint trip, since `CPU::step` consumes the suppression
+        // on its next call regardless of whether that step landed on a
+        // registered breakpoint
+        if let StopReason::Breakpoint(_) = reason {
+          cpu.resume_past_breakpoint();
+        }
+      }
+    }
+  }
+
+  pub fn register_dump(&mut self, cpu: &CPU) -> String {
+    self.last_command = Some(Command::Registers);
+
+    cpu.register_dump()
+  }
+
+  pub fn memory_dump(&mut self, cpu: &mut CPU, start: u32, len: u32) -> String {
+    self.last_command = Some(Command::MemoryDump(start, len));
+
+    cpu.memory_dump(start, len)
+  }
+}
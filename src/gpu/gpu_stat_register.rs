@@ -139,4 +139,89 @@ impl GpuStatRegister {
 
     result
   }
+
+  pub fn save_state(&self, writer: &mut crate::cpu::savestate::SaveWriter) {
+    writer.write_u8(self.texture_x_base);
+    writer.write_u8(self.texture_y_base1);
+    writer.write_u8(self.texture_y_base2);
+    writer.write_u8(self.semi_transparency);
+    writer.write_u8(self.texture_colors as u8);
+    writer.write_bool(self.dither_enabled);
+    writer.write_bool(self.draw_to_display);
+    writer.write_bool(self.force_mask_bit);
+    writer.write_bool(self.preserved_masked_pixels);
+    writer.write_u8(self.interlace_field as u8);
+    writer.write_bool(self.reverse_flag);
+    writer.write_u8(self.hres1);
+    writer.write_u8(self.hres2);
+    writer.write_u8(self.vres);
+    writer.write_u8(self.video_mode as u8);
+    writer.write_u8(self.display_color_depth as u8);
+    writer.write_bool(self.vertical_interlace);
+    writer.write_bool(self.display_enable);
+    writer.write_bool(self.irq_enabled);
+    writer.write_u8(self.dma_dir as u8);
+    writer.write_bool(self.ready_for_command);
+    writer.write_bool(self.ready_vram_to_cpu);
+    writer.write_bool(self.ready_rcv_dma_block);
+    writer.write_bool(self.even_odd);
+  }
+
+  pub fn load_state(&mut self, reader: &mut crate::cpu::savestate::SaveReader) {
+    self.texture_x_base = reader.read_u8();
+    self.texture_y_base1 = reader.read_u8();
+    self.texture_y_base2 = reader.read_u8();
+    self.semi_transparency = reader.read_u8();
+
+    self.texture_colors = match reader.read_u8() {
+      0 => TextureColors::FourBit,
+      1 => TextureColors::EightBit,
+      2 => TextureColors::FifteenBit,
+      n => panic!("unhandled texture depth received: {n}")
+    };
+
+    self.dither_enabled = reader.read_bool();
+    self.draw_to_display = reader.read_bool();
+    self.force_mask_bit = reader.read_bool();
+    self.preserved_masked_pixels = reader.read_bool();
+
+    self.interlace_field = match reader.read_u8() {
+      0 => Field::Bottom,
+      1 => Field::Top,
+      n => panic!("unhandled interlace field received: {n}")
+    };
+
+    self.reverse_flag = reader.read_bool();
+    self.hres1 = reader.read_u8();
+    self.hres2 = reader.read_u8();
+    self.vres = reader.read_u8();
+
+    self.video_mode = match reader.read_u8() {
+      0 => VideoMode::Ntsc,
+      1 => VideoMode::Pal,
+      n => panic!("unhandled video mode received: {n}")
+    };
+
+    self.display_color_depth = match reader.read_u8() {
+      0 => ColorDepth::FifteenBit,
+      1 => ColorDepth::TwentyFourBit,
+      n => panic!("unhandled color depth received: {n}")
+    };
+
+    self.vertical_interlace = reader.read_bool();
+    self.display_enable = reader.read_bool();
+    self.irq_enabled = reader.read_bool();
+
+    self.dma_dir = match reader.read_u8() {
+      0 => DmaDirection::Off,
+      2 => DmaDirection::CputoGP0,
+      3 => DmaDirection::GpuReadToCpu,
+      n => panic!("unhandled dma direction received: {n}")
+    };
+
+    self.ready_for_command = reader.read_bool();
+    self.ready_vram_to_cpu = reader.read_bool();
+    self.ready_rcv_dma_block = reader.read_bool();
+    self.even_odd = reader.read_bool();
+  }
 }
\ No newline at end of file